@@ -0,0 +1,85 @@
+//! Aktionen, die der Reaktor beim Fälligwerden eines Ereignisses ausführt.
+
+use std::process::Command;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Wartezeit, mit der `Shutdown`/`Restart` bei Windows angemeldet werden.
+/// Ohne sie (`/t 0`) schaltet Windows praktisch sofort ab, sodass ein später
+/// ausgelöstes `shutdown /a` keine echte Chance mehr hat, es abzubrechen.
+pub const ABORT_GRACE: Duration = Duration::from_secs(20);
+
+/// Eine vom Scheduler ausführbare Aktion. Wird als Teil eines `ScheduleEntry`
+/// in der Config gespeichert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Shutdown,
+    Restart,
+    Sleep,
+    Hibernate,
+    LogOff,
+    Lock,
+}
+
+impl Action {
+    /// Alle Varianten, in der Reihenfolge, in der sie in der GUI angeboten werden.
+    pub const ALL: [Action; 6] = [
+        Action::Shutdown,
+        Action::Restart,
+        Action::Sleep,
+        Action::Hibernate,
+        Action::LogOff,
+        Action::Lock,
+    ];
+
+    /// Anzeigename für die GUI (Combo-Box, Statuszeilen).
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Shutdown => "Herunterfahren",
+            Action::Restart => "Neustart",
+            Action::Sleep => "Energie sparen",
+            Action::Hibernate => "Ruhezustand",
+            Action::LogOff => "Abmelden",
+            Action::Lock => "Sperren",
+        }
+    }
+
+    /// Ob für diese Aktion bereits ein `shutdown`-Befehl angemeldet wird, der
+    /// sich per `shutdown /a` wieder abbrechen lässt.
+    pub fn is_abortable(&self) -> bool {
+        matches!(self, Action::Shutdown | Action::Restart)
+    }
+
+    /// Führt die Aktion aus (blockierend, wird vom Reaktor-Thread aufgerufen).
+    pub fn run(&self) {
+        println!("Führe Aktion aus: {}", self.label());
+        let grace = ABORT_GRACE.as_secs().to_string();
+        match self {
+            Action::Shutdown => {
+                let _ = Command::new("shutdown").args(&["/s", "/t", &grace]).status();
+            }
+            Action::Restart => {
+                let _ = Command::new("shutdown").args(&["/r", "/t", &grace]).status();
+            }
+            Action::Sleep => {
+                let _ = Command::new("rundll32.exe")
+                    .args(&["powrprof.dll,SetSuspendState", "0,1,0"])
+                    .status();
+            }
+            Action::Hibernate => {
+                let _ = Command::new("rundll32.exe")
+                    .args(&["powrprof.dll,SetSuspendState", "1,1,0"])
+                    .status();
+            }
+            Action::LogOff => {
+                let _ = Command::new("shutdown").args(&["/l"]).status();
+            }
+            Action::Lock => {
+                let _ = Command::new("rundll32.exe")
+                    .args(&["user32.dll,LockWorkStation"])
+                    .status();
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Persistente Konfiguration: ein Zeitplan ist eine Liste von Einträgen, von
+//! denen jeder über eine Wochentags-Bitmaske an mehreren Tagen zur selben
+//! Uhrzeit feuern kann. Das ersetzt die frühere `HashMap<String, Vec<String>>`,
+//! die pro Wochentag nur eine einzige Uhrzeit zuließ.
+
+use chrono::{Duration as ChronoDuration, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::action::Action;
+use crate::recurrence::IntervalUnit;
+
+/// Stabile Kennung eines Zeitplan-Eintrags, unabhängig von seiner Position in
+/// `Config.entries`. Wird u. a. als Schlüssel in `state.json` verwendet, um
+/// verpasste Termine pro Eintrag nachzuverfolgen.
+pub type EntryId = u64;
+
+/// Bit-Position je Wochentag: Bit 0 = Montag ... Bit 6 = Sonntag.
+fn bit_for(weekday: Weekday) -> u8 {
+    1 << weekday.num_days_from_monday()
+}
+
+/// Ein einzelner Zeitplan-Eintrag: an welchen Wochentagen (Bitmaske) zu
+/// welcher Uhrzeit welche Aktion ausgeführt werden soll.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleEntry {
+    pub id: EntryId,
+    /// Wochentags-Bitmaske, persistiert als kompakte Liste wie "1,2,3,4,5"
+    /// (1 = Montag ... 7 = Sonntag).
+    #[serde(with = "days_as_compact_string")]
+    pub days: u8,
+    pub time: NaiveTime,
+    pub action: Action,
+    /// Vorlaufzeiten für Countdown-Warnungen vor der Aktion, absteigend
+    /// sortiert (z. B. 10, 5 und 1 Minute vorher).
+    #[serde(with = "duration_vec_as_seconds", default = "default_warn_leads")]
+    pub warn_leads: Vec<ChronoDuration>,
+}
+
+impl ScheduleEntry {
+    /// Prüft, ob dieser Eintrag an `weekday` feuern soll.
+    pub fn matches(&self, weekday: Weekday) -> bool {
+        self.days & bit_for(weekday) != 0
+    }
+}
+
+/// Ein Intervall-Job: feuert alle `n` `unit`, optional auf einen Anker
+/// innerhalb der nächsthöheren Einheit ausgerichtet (z. B. Minuten-Jobs immer
+/// zur selben Sekunde). `at` speichert die rohe, bereits validierte
+/// Ankerangabe (z. B. ":15"), aus der beim Aktivieren erneut eine
+/// `Recurrence` gebaut wird.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IntervalJob {
+    pub id: EntryId,
+    pub n: u32,
+    pub unit: IntervalUnit,
+    pub at: Option<String>,
+    pub action: Action,
+    /// Vorlaufzeiten für Countdown-Warnungen vor der Aktion, absteigend
+    /// sortiert (z. B. 10, 5 und 1 Minute vorher).
+    #[serde(with = "duration_vec_as_seconds", default = "default_warn_leads")]
+    pub warn_leads: Vec<ChronoDuration>,
+}
+
+/// Default-Vorwarnung, falls eine gespeicherte Config das Feld noch nicht
+/// kennt: eine einzelne Warnung 5 Minuten vorher, wie früher fest einprogrammiert.
+pub fn default_warn_leads() -> Vec<ChronoDuration> {
+    vec![ChronoDuration::minutes(5)]
+}
+
+mod days_as_compact_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(days: &u8, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let compact = (0..7u8)
+            .filter(|bit| days & (1 << bit) != 0)
+            .map(|bit| (bit + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        serializer.serialize_str(&compact)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u8, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let compact = String::deserialize(deserializer)?;
+        let mask = compact
+            .split(',')
+            .filter_map(|part| part.trim().parse::<u8>().ok())
+            .filter(|day| (1..=7).contains(day))
+            .fold(0u8, |mask, day| mask | (1 << (day - 1)));
+        Ok(mask)
+    }
+}
+
+/// (De-)Serialisiert eine Liste von Vorlaufzeiten als Sekunden, absteigend
+/// sortiert, damit der Reaktor die Warn-Ereignisse in der richtigen
+/// Reihenfolge vor der Aktion einplanen kann.
+mod duration_vec_as_seconds {
+    use chrono::Duration as ChronoDuration;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(leads: &[ChronoDuration], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        leads
+            .iter()
+            .map(ChronoDuration::num_seconds)
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<ChronoDuration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut seconds = Vec::<i64>::deserialize(deserializer)?;
+        seconds.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(seconds.into_iter().map(ChronoDuration::seconds).collect())
+    }
+}
+
+/// Konfigurationsstruktur: enthält den Zeitplan als Liste von Einträgen.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Config {
+    pub entries: Vec<ScheduleEntry>,
+    /// Intervall-Jobs wie "alle 45 Minuten sperren", unabhängig vom
+    /// wochentagsbasierten Zeitplan.
+    #[serde(default)]
+    pub interval_jobs: Vec<IntervalJob>,
+    /// Zähler zur Vergabe stabiler `EntryId`s für neue Einträge.
+    #[serde(default)]
+    pub next_entry_id: EntryId,
+    /// Wie viele Tage rückwirkend beim Start nach verpassten Terminen gesucht
+    /// wird (Standard: 1, d. h. heute und gestern).
+    #[serde(default = "default_restore_offset_days")]
+    pub restore_offset_days: i64,
+}
+
+fn default_restore_offset_days() -> i64 {
+    1
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            interval_jobs: Vec::new(),
+            next_entry_id: 0,
+            restore_offset_days: default_restore_offset_days(),
+        }
+    }
+}
+
+impl Config {
+    /// Vergibt eine neue, für diese Config eindeutige `EntryId`.
+    pub fn alloc_id(&mut self) -> EntryId {
+        let id = self.next_entry_id;
+        self.next_entry_id += 1;
+        id
+    }
+}
+
+/// Hilfsfunktion: Läd die Config aus config.json oder erzeugt einen Default.
+pub fn load_config() -> Config {
+    fs::read_to_string("config.json")
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Hilfsfunktion: Speichert die Config in config.json.
+pub fn save_config(config: &Config) {
+    if let Ok(json) = serde_json::to_string_pretty(config) {
+        let _ = fs::write("config.json", json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_bitmask_round_trips_through_compact_string() {
+        // Montag bis Freitag: Bits 0..=4 gesetzt.
+        let days: u8 = (0..5).fold(0u8, |mask, bit| mask | (1 << bit));
+        let entry = ScheduleEntry {
+            id: 1,
+            days,
+            time: NaiveTime::from_hms(22, 0, 0),
+            action: Action::Shutdown,
+            warn_leads: default_warn_leads(),
+        };
+
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains("\"1,2,3,4,5\""));
+
+        let restored: ScheduleEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.days, days);
+        for weekday in [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri] {
+            assert!(restored.matches(weekday));
+        }
+        assert!(!restored.matches(Weekday::Sat));
+        assert!(!restored.matches(Weekday::Sun));
+    }
+}
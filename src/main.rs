@@ -1,82 +1,238 @@
 #![allow(deprecated)]
 
-use chrono::{Local, NaiveTime, Datelike, Duration as ChronoDuration};
+mod action;
+mod config;
+mod reactor;
+mod recurrence;
+mod state;
+
+use chrono::{DateTime, Local, NaiveTime, Datelike, Weekday, Duration as ChronoDuration};
 use eframe::{egui, App};
-use serde::{Deserialize, Serialize};
-use std::{
-    collections::HashMap,
-    fs,
-    process::Command,
-    sync::{Arc, Mutex},
-    thread,
-    time::Duration,
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use winapi::um::winuser::{
+    MessageBoxA, IDYES, MB_ICONQUESTION, MB_YESNO,
 };
-use winapi::um::winuser::{MessageBoxA, MB_OK, MB_ICONWARNING};
 use regex::Regex;
 
+use action::Action;
+use config::{default_warn_leads, load_config, save_config, Config, EntryId, IntervalJob, ScheduleEntry};
+use reactor::{Event, EventId, EventKind, Reactor};
+use recurrence::{every, IntervalUnit, Recurrence};
+use state::{load_state, save_state, RunState};
+
+/// Alle Wochentage in Anzeige-Reihenfolge, zusammen mit ihrer Kurzbezeichnung.
+/// Die Bit-Position im Tages-Bitmask entspricht dem Index in diesem Array.
+const WEEKDAYS: [(Weekday, &str); 7] = [
+    (Weekday::Mon, "Mo"),
+    (Weekday::Tue, "Di"),
+    (Weekday::Wed, "Mi"),
+    (Weekday::Thu, "Do"),
+    (Weekday::Fri, "Fr"),
+    (Weekday::Sat, "Sa"),
+    (Weekday::Sun, "So"),
+];
 
-/// Konfigurationsstruktur: enthält den Zeitplan für jeden Wochentag
-#[derive(Serialize, Deserialize, Debug, Default)]
-struct Config {
-    // Für jeden Wochentag wird ein Array gespeichert. Wir nutzen hier nur das erste Element.
-    schedule: HashMap<String, Vec<String>>,
+/// Berechnet den nächsten Zeitpunkt, zu dem `target_time` eintritt.
+/// Ist die Zielzeit heute bereits vergangen, wird der morgige Tag angenommen.
+fn next_occurrence(target_time: &NaiveTime) -> chrono::DateTime<Local> {
+    let now = Local::now();
+    let mut target = now.date().and_time(*target_time).unwrap();
+    if target <= now {
+        target = target + ChronoDuration::days(1);
+    }
+    target
 }
 
-/// Hilfsfunktion: Läd die Config aus config.json oder erzeugt einen Default.
-fn load_config() -> Config {
-    fs::read_to_string("config.json")
-        .ok()
-        .and_then(|data| serde_json::from_str(&data).ok())
-        .unwrap_or_else(|| {
-            let mut schedule = HashMap::new();
-            for day in &[
-                "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday",
-            ] {
-                schedule.insert(day.to_string(), vec!["".to_string()]);
-            }
-            Config { schedule }
-        })
+/// Berechnet den nächsten Zeitpunkt, zu dem `target_time` an `weekday` eintritt.
+fn next_occurrence_on(target_time: &NaiveTime, weekday: Weekday) -> chrono::DateTime<Local> {
+    let mut next = next_occurrence(target_time);
+    while next.weekday() != weekday {
+        next = next + ChronoDuration::days(1);
+    }
+    next
 }
 
-/// Hilfsfunktion: Speichert die Config in config.json.
-fn save_config(config: &Config) {
-    if let Ok(json) = serde_json::to_string_pretty(config) {
-        let _ = fs::write("config.json", json);
+/// Berechnet die Anzahl der Sekunden von jetzt bis zur Zielzeit (Format "HH:MM").
+/// Ist die Zielzeit bereits vergangen, wird der morgige Tag angenommen.
+fn get_delay_seconds(target_time: &NaiveTime) -> i64 {
+    (next_occurrence(target_time) - Local::now()).num_seconds()
+}
+
+/// Berechnet den letzten Zeitpunkt, zu dem `target_time` an `weekday` bereits
+/// eingetreten ist (also `<= now`). Wird für das Nachholen verpasster Termine
+/// beim Programmstart benötigt.
+fn most_recent_occurrence_on(
+    target_time: &NaiveTime,
+    weekday: Weekday,
+    now: DateTime<Local>,
+) -> DateTime<Local> {
+    let mut candidate = now.date().and_time(*target_time).unwrap();
+    if candidate > now {
+        candidate = candidate - ChronoDuration::days(1);
     }
+    while candidate.weekday() != weekday {
+        candidate = candidate - ChronoDuration::days(1);
+    }
+    candidate
 }
 
-/// Zeigt eine Windows-Benachrichtigung via MessageBox (winapi).
-fn show_notification(message: &str) {
+/// Fragt den Benutzer per MessageBox, ob ein verpasster Termin jetzt
+/// nachgeholt werden soll.
+fn confirm_missed(message: &str) -> bool {
     use std::ffi::CString;
     let c_message = CString::new(message).unwrap();
-    let c_title = CString::new("Shutdown Warning").unwrap();
-    unsafe {
+    let c_title = CString::new("Verpasster Termin").unwrap();
+    let result = unsafe {
         MessageBoxA(
             std::ptr::null_mut(),
             c_message.as_ptr(),
             c_title.as_ptr(),
-            MB_OK | MB_ICONWARNING,
-        );
+            MB_YESNO | MB_ICONQUESTION,
+        )
+    };
+    result == IDYES
+}
+
+/// Trägt das tatsächliche Feuern eines Eintrags in den Laufzeitzustand ein
+/// und schreibt ihn sofort nach state.json, damit ein Absturz direkt danach
+/// keinen doppelten Nachhol-Vorgang auslöst.
+fn record_fired(state: &Arc<Mutex<RunState>>, entry_id: EntryId, at: DateTime<Local>) {
+    let mut state = state.lock().unwrap();
+    state.last_fired.insert(entry_id, at);
+    save_state(&state);
+}
+
+/// Formatiert Vorlaufzeiten für das Bearbeitungsformular als kommagetrennte
+/// Minutenliste, z. B. `[10min, 5min, 1min]` -> "10,5,1".
+fn format_warn_leads(leads: &[ChronoDuration]) -> String {
+    leads
+        .iter()
+        .map(|lead| (lead.num_seconds() / 60).to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Liest eine kommagetrennte Minutenliste wie "10,5,1" ein und liefert sie
+/// absteigend sortiert als Vorlaufzeiten zurück. Eine leere Eingabe bedeutet
+/// keine Vorwarnung.
+fn parse_warn_leads(spec: &str) -> Result<Vec<ChronoDuration>, String> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut minutes = Vec::new();
+    for part in spec.split(',') {
+        let minutes_value: i64 = part
+            .trim()
+            .parse()
+            .map_err(|_| format!("Ungültige Vorlaufzeit '{}', bitte Minutenzahlen eingeben.", part))?;
+        if minutes_value <= 0 {
+            return Err(format!("Vorlaufzeit '{}' muss positiv sein.", part));
+        }
+        minutes.push(minutes_value);
     }
+    minutes.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(minutes.into_iter().map(ChronoDuration::minutes).collect())
 }
 
-/// Führt den Shutdown aus (benötigt Administratorrechte).
-fn shutdown_pc() {
-    println!("Fahre den PC herunter...");
-    let _ = Command::new("shutdown").args(&["/s", "/t", "0"]).status();
+/// Eine Zeile im Zeitplan-Bearbeitungsformular: pro Wochentag ein Häkchen
+/// plus eine gemeinsame Uhrzeit für diese Zeile.
+struct ScheduleRowEdit {
+    id: EntryId,
+    days: [bool; 7],
+    time: String,
+    action: Action,
+    /// Vorlaufzeiten als kommagetrennte Minutenliste, z. B. "10,5,1".
+    warn_leads: String,
 }
 
-/// Berechnet die Anzahl der Sekunden von jetzt bis zur Zielzeit (Format "HH:MM").
-/// Ist die Zielzeit bereits vergangen, wird der morgige Tag angenommen.
-fn get_delay_seconds(target_time: &NaiveTime) -> i64 {
-    let now = Local::now();
-    let mut target = now.date().and_time(*target_time);
-    if let Some(t) = target {
-        if t <= now {
-            target = Some(t + ChronoDuration::days(1));
+impl ScheduleRowEdit {
+    fn new(id: EntryId) -> Self {
+        Self {
+            id,
+            days: [false; 7],
+            time: String::new(),
+            action: Action::Shutdown,
+            warn_leads: format_warn_leads(&default_warn_leads()),
+        }
+    }
+
+    fn from_entry(entry: &ScheduleEntry) -> Self {
+        let mut days = [false; 7];
+        for (i, (weekday, _)) in WEEKDAYS.iter().enumerate() {
+            days[i] = entry.matches(*weekday);
+        }
+        Self {
+            id: entry.id,
+            days,
+            time: entry.time.format("%H:%M").to_string(),
+            action: entry.action,
+            warn_leads: format_warn_leads(&entry.warn_leads),
+        }
+    }
+}
+
+/// Eine Zeile im Intervall-Job-Bearbeitungsformular.
+struct IntervalRowEdit {
+    id: EntryId,
+    n: String,
+    unit: IntervalUnit,
+    /// Ankerangabe als Rohtext (z. B. ":15"), leer = kein Anker.
+    at: String,
+    action: Action,
+    /// Vorlaufzeiten als kommagetrennte Minutenliste, z. B. "10,5,1".
+    warn_leads: String,
+}
+
+impl IntervalRowEdit {
+    fn new(id: EntryId) -> Self {
+        Self {
+            id,
+            n: "1".to_owned(),
+            unit: IntervalUnit::Hours,
+            at: String::new(),
+            action: Action::Shutdown,
+            warn_leads: format_warn_leads(&default_warn_leads()),
+        }
+    }
+
+    fn from_job(job: &IntervalJob) -> Self {
+        Self {
+            id: job.id,
+            n: job.n.to_string(),
+            unit: job.unit,
+            at: job.at.clone().unwrap_or_default(),
+            action: job.action,
+            warn_leads: format_warn_leads(&job.warn_leads),
         }
     }
-    (target.unwrap() - now).num_seconds()
+}
+
+/// Baut aus einem Intervall-Job die passende `Recurrence`.
+fn interval_recurrence(n: u32, unit: IntervalUnit, at: &Option<String>) -> Result<Recurrence, String> {
+    let job = match unit {
+        IntervalUnit::Seconds => every(n).seconds(),
+        IntervalUnit::Minutes => every(n).minutes(),
+        IntervalUnit::Hours => every(n).hours(),
+        IntervalUnit::Days => every(n).days(),
+    };
+    let job = match at {
+        Some(spec) if !spec.trim().is_empty() => job.at(spec)?,
+        _ => job,
+    };
+    job.build()
+}
+
+/// Ein gerade angezeigter Vorwarnungs-Dialog für ein bevorstehendes
+/// Aktions-Ereignis. `target` ist die Id des Aktions-Ereignisses im Reaktor,
+/// an das "Verschieben" und "Abbrechen" weitergereicht werden.
+#[derive(Clone)]
+struct ActiveWarning {
+    target: EventId,
+    action: Action,
+    /// Geschätzter Zeitpunkt der Aktion (Zeitpunkt der Warnung + ihre Vorlaufzeit).
+    fires_at: DateTime<Local>,
 }
 
 /// Struktur für die GUI. Zusätzlich zur bisherigen manuellen Shutdown-Funktionalität
@@ -84,130 +240,238 @@ fn get_delay_seconds(target_time: &NaiveTime) -> i64 {
 struct ShutdownApp {
     // Für manuellen Shutdown
     manual_input: String,
+    manual_action: Action,
     manual_status: String,
     // Übersicht manuell geplanter Shutdowns (für dieses Beispiel nicht weiter genutzt)
     manual_tasks: Arc<Mutex<Vec<(String, i64)>>>,
+    // Einziger Hintergrund-Reaktor, der alle anstehenden Ereignisse verwaltet.
+    reactor: Reactor,
+    // Laufzeitzustand (zuletzt gefeuerte Einträge), geteilt mit dem Reaktor-Thread.
+    state: Arc<Mutex<RunState>>,
     // Config (Zeitplan)
     config: Config,
     // Status-Nachricht zum Zeitplan
     schedule_status: String,
-    // Für jeden Wochentag: (aktiv, shutdown_time)
-    schedule_edit: HashMap<String, (bool, String)>,
+    // Bearbeitungszeilen: je eine Wochentags-Auswahl plus Uhrzeit.
+    schedule_edit: Vec<ScheduleRowEdit>,
+    // Bearbeitungszeilen für Intervall-Jobs ("alle N Stunden/Minuten").
+    interval_edit: Vec<IntervalRowEdit>,
+    // Gerade angezeigte Vorwarnungs-Dialoge, Schlüssel ist die Id des
+    // betroffenen Aktions-Ereignisses. Wird vom Reaktor-Thread über
+    // `set_on_warning` befüllt.
+    active_warnings: Arc<Mutex<HashMap<EventId, ActiveWarning>>>,
+    // Vom `CreationContext` übernommener `egui::Context`, damit der
+    // Reaktor-Thread einen Repaint anstoßen kann, sobald eine Vorwarnung
+    // eintrifft, statt auf den nächsten ohnehin fälligen Redraw zu warten.
+    egui_ctx: Arc<Mutex<Option<egui::Context>>>,
 }
 
 impl Default for ShutdownApp {
     fn default() -> Self {
         let config = load_config();
-        let mut schedule_edit = HashMap::new();
-        // Erstelle Bearbeitungsdaten basierend auf config
-        for day in &[
-            "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday",
-        ] {
-            // Wir erwarten ein einzelnes Element im Array
-            let time_opt = config.schedule.get(*day).and_then(|v| v.get(0));
-            let active = match time_opt {
-                Some(t) if !t.trim().is_empty() => true,
-                _ => false,
-            };
-            let time_value = time_opt.cloned().unwrap_or_else(|| "".to_string());
-            schedule_edit.insert(day.to_string(), (active, time_value));
+        let schedule_edit = config.entries.iter().map(ScheduleRowEdit::from_entry).collect();
+        let interval_edit = config.interval_jobs.iter().map(IntervalRowEdit::from_job).collect();
+
+        let reactor = Reactor::new();
+        let state = Arc::new(Mutex::new(load_state()));
+        {
+            let state = Arc::clone(&state);
+            reactor.set_on_fired(move |entry_id, at| record_fired(&state, entry_id, at));
         }
-        Self {
+
+        let active_warnings = Arc::new(Mutex::new(HashMap::new()));
+        let egui_ctx: Arc<Mutex<Option<egui::Context>>> = Arc::new(Mutex::new(None));
+        {
+            let active_warnings = Arc::clone(&active_warnings);
+            let egui_ctx = Arc::clone(&egui_ctx);
+            reactor.set_on_warning(move |target, _entry_id, action, lead| {
+                active_warnings.lock().unwrap().insert(
+                    target,
+                    ActiveWarning {
+                        target,
+                        action,
+                        fires_at: Local::now() + lead,
+                    },
+                );
+                // Ohne diesen Anstoß zeichnet eframe im reaktiven Modus erst
+                // wieder, sobald Eingaben eintreffen, sodass der Dialog bis
+                // dahin unsichtbar bliebe.
+                if let Some(ctx) = &*egui_ctx.lock().unwrap() {
+                    ctx.request_repaint();
+                }
+            });
+        }
+
+        let mut app = Self {
             manual_input: "".to_owned(),
+            manual_action: Action::Shutdown,
             manual_status: "Kein manueller Shutdown geplant.".to_owned(),
             manual_tasks: Arc::new(Mutex::new(Vec::new())),
+            reactor,
+            state,
             config,
             schedule_status: "".to_owned(),
             schedule_edit,
-        }
+            interval_edit,
+            active_warnings,
+            egui_ctx,
+        };
+        // Ohne diesen Aufruf bliebe die Heap nach einem Neustart leer, bis
+        // der Benutzer "Zeitplan speichern" oder "neu laden" klickt - der
+        // wiederkehrende Zeitplan muss aber bereits ab dem Programmstart laufen.
+        app.activate_schedules();
+        // Erst danach nachholen: `replace_all` verwirft alle Ereignisse mit
+        // `entry_id`, also auch bereits eingeplante Nachhol-Ereignisse.
+        app.restore_missed_schedules();
+        app
     }
 }
 
 impl ShutdownApp {
-    /// Plant einen manuellen Shutdown anhand der eingegebenen Zeit.
+    /// Übernimmt den `egui::Context` aus dem `CreationContext`, damit der
+    /// Reaktor-Thread per `request_repaint()` einen Redraw anstoßen kann,
+    /// sobald eine Vorwarnung eintrifft.
+    fn set_egui_ctx(&self, ctx: egui::Context) {
+        *self.egui_ctx.lock().unwrap() = Some(ctx);
+    }
+
+    /// Plant eine manuelle Aktion anhand der eingegebenen Zeit.
+    /// Die eigentliche Wartezeit und Warnung übernimmt der Reaktor.
     fn schedule_manual_shutdown(&mut self) {
         if let Ok(target_time) = NaiveTime::parse_from_str(self.manual_input.trim(), "%H:%M") {
+            let next = next_occurrence(&target_time);
             let delay = get_delay_seconds(&target_time);
-            self.manual_status = format!("Shutdown in {} Sekunden geplant um {}.", delay, self.manual_input);
+            self.manual_status = format!(
+                "{} in {} Sekunden geplant um {}.",
+                self.manual_action.label(),
+                delay,
+                self.manual_input
+            );
             {
                 let mut tasks = self.manual_tasks.lock().unwrap();
                 tasks.push((self.manual_input.clone(), delay));
             }
-            thread::spawn(move || {
-                if delay > 300 {
-                    thread::sleep(Duration::from_secs((delay - 300) as u64));
-                    show_notification("Der Rechner fährt in 5 Minuten herunter!\nBitte speichere deine Arbeit.");
-                    thread::sleep(Duration::from_secs(300));
-                } else {
-                    thread::sleep(Duration::from_secs(delay as u64));
-                }
-                shutdown_pc();
+            self.reactor.push(Event {
+                id: self.reactor.next_id(),
+                next,
+                action: self.manual_action,
+                recur: Recurrence::Once,
+                entry_id: None,
+                kind: EventKind::Action,
+                warn_leads: default_warn_leads(),
             });
         } else {
             self.manual_status = "Ungültiges Zeitformat! Bitte HH:MM eingeben.".to_owned();
         }
     }
 
-    /// Aktiviert wiederkehrende Shutdowns anhand der Konfiguration.
-    /// In diesem Beispiel wird jeweils nur eine Zeit pro Tag verwendet.
+    /// Holt Termine nach, die gefeuert hätten, während der PC aus war oder die
+    /// App nicht lief. Durchsucht jeden Eintrag nach seiner letzten Fälligkeit
+    /// innerhalb von `restore_offset_days`; ist sie in der Vergangenheit und
+    /// neuer als der zuletzt vermerkte `last_fired`-Zeitpunkt, wird der
+    /// Benutzer gefragt, ob die Aktion jetzt nachgeholt werden soll.
+    fn restore_missed_schedules(&mut self) {
+        let now = Local::now();
+        let window_start = (now.date() - ChronoDuration::days(self.config.restore_offset_days.max(0)))
+            .and_hms(0, 0, 0);
+
+        for entry in self.config.entries.clone() {
+            let candidate = WEEKDAYS
+                .iter()
+                .filter(|(weekday, _)| entry.matches(*weekday))
+                .map(|(weekday, _)| most_recent_occurrence_on(&entry.time, *weekday, now))
+                .max();
+            let candidate = match candidate {
+                Some(candidate) if candidate >= window_start => candidate,
+                _ => continue,
+            };
+            let already_handled = self
+                .state
+                .lock()
+                .unwrap()
+                .last_fired
+                .get(&entry.id)
+                .map_or(false, |last| *last >= candidate);
+            if already_handled {
+                continue;
+            }
+            let message = format!(
+                "Ein Termin '{}' um {} wurde verpasst (zuletzt fällig: {}). Jetzt nachholen?",
+                entry.action.label(),
+                entry.time.format("%H:%M"),
+                candidate.format("%d.%m. %H:%M")
+            );
+            if confirm_missed(&message) {
+                // Über den Reaktor statt direkt ausführen, damit z. B.
+                // `armed_shutdown` gesetzt wird und die Gnadenfrist aus
+                // `Action::run` greift - so lässt sich auch ein
+                // nachgeholter Shutdown noch per "Abbrechen" stoppen.
+                self.reactor.push(Event {
+                    id: self.reactor.next_id(),
+                    next: candidate,
+                    action: entry.action,
+                    recur: Recurrence::Once,
+                    entry_id: Some(entry.id),
+                    kind: EventKind::Action,
+                    warn_leads: Vec::new(),
+                });
+            }
+        }
+    }
+
+    /// Aktiviert wiederkehrende Shutdowns und Intervall-Jobs anhand der
+    /// Konfiguration, indem die Ereignisse des Reaktors komplett durch die
+    /// aktuelle Config ersetzt werden. So verdoppeln wiederholtes Bearbeiten
+    /// oder Neuladen nichts. Jeder Wochentags-Eintrag kann über seine Bitmaske
+    /// mehrere Tage abdecken; pro gesetztem Bit wird eine eigene Wiederholung
+    /// eingeplant. Intervall-Jobs laufen in derselben Heap mit.
     fn activate_schedules(&mut self) {
-        let schedule = self.config.schedule.clone();
-        for (day, times) in schedule {
-            // Wir nutzen hier nur den ersten Eintrag
-            if let Some(time_str) = times.get(0) {
-                if !time_str.trim().is_empty() {
-                    if let Ok(target_time) = NaiveTime::parse_from_str(time_str, "%H:%M") {
-                        // Berechne, wie viele Tage bis zum gewünschten Wochentag gewartet werden müssen.
-                        let now = Local::now();
-                        let today = now.weekday().to_string(); // z. B. "Monday"
-                        let weekday_order = vec![
-                            "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday",
-                        ];
-                        let today_index = match weekday_order.iter().position(|&d| d == today) {
-                            Some(idx) => idx,
-                            None => {
-                                eprintln!("Fehler: Heute ('{}') ist nicht in weekday_order enthalten!", today);
-                                continue;
-                            }
-                        };
-                        
-                        // Position des Zieltages ermitteln
-                        let target_index = match weekday_order.iter().position(|&d| d == day) {
-                            Some(idx) => idx,
-                            None => {
-                                eprintln!("Fehler: Zieltag '{}' nicht in weekday_order enthalten!", day);
-                                continue;
-                            }
-                        };
-                        let days_to_wait = if target_index >= today_index {
-                            target_index - today_index
-                        } else {
-                            7 - today_index + target_index
-                        } as i64;
-                        let mut delay = get_delay_seconds(&target_time) + days_to_wait * 24 * 3600;
-                        if delay < 0 {
-                            delay += 24 * 3600;
-                        }
-                        println!("Geplanter Shutdown: {} um {} in {} Sekunden.", day, time_str, delay);
-                        thread::spawn(move || loop {
-                            if delay > 300 {
-                                thread::sleep(Duration::from_secs((delay - 300) as u64));
-                                show_notification("Der Rechner fährt in 5 Minuten herunter!\nBitte speichere deine Arbeit.");
-                                thread::sleep(Duration::from_secs(300));
-                            } else {
-                                thread::sleep(Duration::from_secs(delay as u64));
-                            }
-                            shutdown_pc();
-                            // Nächster Shutdown in 7 Tagen
-                            delay = 7 * 24 * 3600;
+        let mut events = Vec::new();
+        for entry in &self.config.entries {
+            for (weekday, label) in WEEKDAYS {
+                if !entry.matches(weekday) {
+                    continue;
+                }
+                let next = next_occurrence_on(&entry.time, weekday);
+                println!("Geplanter Vorgang: {} um {} ({}).", label, entry.time, next);
+                events.push(Event {
+                    id: self.reactor.next_id(),
+                    next,
+                    action: entry.action,
+                    recur: Recurrence::Weekly(weekday),
+                    entry_id: Some(entry.id),
+                    kind: EventKind::Action,
+                    warn_leads: entry.warn_leads.clone(),
+                });
+            }
+        }
+        for job in &self.config.interval_jobs {
+            match interval_recurrence(job.n, job.unit, &job.at) {
+                Ok(recur) => {
+                    if let Some(next) = recur.next_after(Local::now()) {
+                        println!(
+                            "Geplanter Intervall-Job: alle {} {} ({}) ({}).",
+                            job.n,
+                            job.unit.label(),
+                            job.action.label(),
+                            next
+                        );
+                        events.push(Event {
+                            id: self.reactor.next_id(),
+                            next,
+                            action: job.action,
+                            recur,
+                            entry_id: Some(job.id),
+                            kind: EventKind::Action,
+                            warn_leads: job.warn_leads.clone(),
                         });
-                    } else {
-                        println!("Ungültiges Zeitformat in config für {}: {}", day, time_str);
                     }
                 }
+                Err(err) => eprintln!("Ungültiger Intervall-Job {}: {}", job.id, err),
             }
         }
-        self.schedule_status = "Wiederkehrende Shutdowns aktiviert.".to_owned();
+        self.reactor.replace_all(events);
+        self.schedule_status = "Wiederkehrende Vorgänge aktiviert.".to_owned();
     }
 
     /// Speichert die Zeitplan-Bearbeitungsdaten in die Config
@@ -217,24 +481,123 @@ impl ShutdownApp {
         // ^\d{2}:\d{2}$ bedeutet: genau 2 Ziffern, ein Doppelpunkt, genau 2 Ziffern.
         let time_regex = Regex::new(r"^\d{2}:\d{2}$").unwrap();
 
-        for (day, (active, time)) in &self.schedule_edit {
-            if *active {
-                if time_regex.is_match(time.trim()) {
-                    self.config.schedule.insert(day.clone(), vec![time.trim().to_string()]);
-                } else {
-                    // Falls das Format ungültig ist, kannst du auch standardmäßig einen leeren Wert
-                    // einsetzen oder eine Fehlermeldung setzen.
-                    println!("Ungültiges Zeitformat für {}: {}. Bitte gib HH:MM ein.", day, time);
-                    self.config.schedule.insert(day.clone(), vec!["".to_string()]);
+        let mut entries = Vec::new();
+        for row in &self.schedule_edit {
+            let days: u8 = row
+                .days
+                .iter()
+                .enumerate()
+                .filter(|(_, active)| **active)
+                .fold(0u8, |mask, (i, _)| mask | (1 << i));
+            if days == 0 {
+                continue;
+            }
+            if !time_regex.is_match(row.time.trim()) {
+                println!("Ungültiges Zeitformat '{}'. Bitte HH:MM eingeben.", row.time);
+                continue;
+            }
+            let time = match NaiveTime::parse_from_str(row.time.trim(), "%H:%M") {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            let warn_leads = match parse_warn_leads(&row.warn_leads) {
+                Ok(leads) => leads,
+                Err(err) => {
+                    println!("Ungültige Vorlaufzeiten: {}", err);
+                    continue;
+                }
+            };
+            entries.push(ScheduleEntry {
+                id: row.id,
+                days,
+                time,
+                action: row.action,
+                warn_leads,
+            });
+        }
+        self.config.entries = entries;
+
+        let mut interval_jobs = Vec::new();
+        for row in &self.interval_edit {
+            let n: u32 = match row.n.trim().parse() {
+                Ok(n) if n > 0 => n,
+                _ => {
+                    println!("Ungültiger Intervall-Wert '{}'. Bitte eine positive Zahl eingeben.", row.n);
+                    continue;
                 }
+            };
+            let at = if row.at.trim().is_empty() {
+                None
             } else {
-                self.config.schedule.insert(day.clone(), vec!["".to_string()]);
+                Some(row.at.trim().to_string())
+            };
+            if let Err(err) = interval_recurrence(n, row.unit, &at) {
+                println!("Ungültiger Intervall-Job: {}", err);
+                continue;
             }
+            let warn_leads = match parse_warn_leads(&row.warn_leads) {
+                Ok(leads) => leads,
+                Err(err) => {
+                    println!("Ungültige Vorlaufzeiten: {}", err);
+                    continue;
+                }
+            };
+            interval_jobs.push(IntervalJob {
+                id: row.id,
+                n,
+                unit: row.unit,
+                at,
+                action: row.action,
+                warn_leads,
+            });
         }
+        self.config.interval_jobs = interval_jobs;
+
         save_config(&self.config);
         self.activate_schedules();
         self.schedule_status = "Zeitplan gespeichert und aktiviert.".to_string();
     }
+
+    /// Zeigt für jede laufende Vorwarnung ein nicht-blockierendes Fenster mit
+    /// Countdown sowie "Verschieben" und "Abbrechen". Ersetzt die frühere,
+    /// den Reaktor-Thread blockierende `MessageBoxA`-Warnung.
+    fn show_warning_dialogs(&mut self, ctx: &egui::Context) {
+        let now = Local::now();
+        let warnings: Vec<ActiveWarning> = {
+            let mut active = self.active_warnings.lock().unwrap();
+            active.retain(|_, warning| warning.fires_at > now);
+            active.values().cloned().collect()
+        };
+
+        for warning in &warnings {
+            let remaining = (warning.fires_at - now).num_seconds().max(0);
+            egui::Window::new(format!("Vorwarnung: {}", warning.action.label()))
+                .id(egui::Id::new(("warning", warning.target)))
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "{} in {} Sekunden.\nBitte speichere deine Arbeit.",
+                        warning.action.label(),
+                        remaining
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Verschieben (15 Min.)").clicked() {
+                            self.reactor.postpone(warning.target, ChronoDuration::minutes(15));
+                            self.active_warnings.lock().unwrap().remove(&warning.target);
+                        }
+                        if ui.button("Abbrechen").clicked() {
+                            self.reactor.cancel(warning.target);
+                            self.active_warnings.lock().unwrap().remove(&warning.target);
+                        }
+                    });
+                });
+        }
+
+        if !warnings.is_empty() {
+            ctx.request_repaint();
+        }
+    }
 }
 
 impl App for ShutdownApp {
@@ -243,10 +606,19 @@ impl App for ShutdownApp {
             ui.heading("Shutdown GUI App");
             ui.separator();
 
-            // Manueller Shutdown
-            ui.label("Manueller Shutdown (Format HH:MM):");
-            ui.text_edit_singleline(&mut self.manual_input);
-            if ui.button("Shutdown manuell planen").clicked() {
+            // Manuelle Aktion
+            ui.label("Manuelle Aktion (Format HH:MM):");
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.manual_input);
+                egui::ComboBox::from_id_source("manual_action")
+                    .selected_text(self.manual_action.label())
+                    .show_ui(ui, |ui| {
+                        for action in Action::ALL {
+                            ui.selectable_value(&mut self.manual_action, action, action.label());
+                        }
+                    });
+            });
+            if ui.button("Aktion manuell planen").clicked() {
                 self.schedule_manual_shutdown();
             }
             ui.label(&self.manual_status);
@@ -254,39 +626,116 @@ impl App for ShutdownApp {
             ui.separator();
             // Übersicht des Wiederkehrenden Zeitplans (aus config.json)
             ui.heading("Wiederkehrender Zeitplan");
-            for (day, times) in &self.config.schedule {
-                ui.horizontal(|ui| {
-                    ui.label(format!("{}:", day));
-                    if times.is_empty() || times[0].trim().is_empty() {
-                        ui.label("Nicht aktiviert".to_string());
-                    } else {
-                        ui.label(times.join(", "));
-                    }
-                });
+            for entry in &self.config.entries {
+                let days = WEEKDAYS
+                    .iter()
+                    .filter(|(weekday, _)| entry.matches(*weekday))
+                    .map(|(_, label)| *label)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ui.label(format!(
+                    "{}: {} ({})",
+                    days,
+                    entry.time.format("%H:%M"),
+                    entry.action.label()
+                ));
             }
             if ui.button("Zeitplan neu laden und aktivieren").clicked() {
                 self.config = load_config();
+                self.schedule_edit = self.config.entries.iter().map(ScheduleRowEdit::from_entry).collect();
+                self.interval_edit = self.config.interval_jobs.iter().map(IntervalRowEdit::from_job).collect();
                 self.activate_schedules();
             }
             ui.label(&self.schedule_status);
 
             ui.separator();
-            // Bearbeiten des Zeitplans – für jeden Wochentag
+            // Bearbeiten des Zeitplans – eine Zeile pro Eintrag mit Tagesauswahl und Uhrzeit.
             ui.heading("Zeitplan bearbeiten");
-            for day in &["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"] {
-                if let Some((active, time)) = self.schedule_edit.get_mut(&day.to_string()) {
-                    ui.horizontal(|ui| {
-                        ui.label(format!("{}:", day));
-                        ui.checkbox(active, "");
-                        ui.label("Uhrzeit (HH:MM):");
-                        ui.text_edit_singleline(time);
-                    });
-                }
+            let mut remove_index = None;
+            for (i, row) in self.schedule_edit.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    for (j, (_, label)) in WEEKDAYS.iter().enumerate() {
+                        ui.checkbox(&mut row.days[j], *label);
+                    }
+                    ui.label("Uhrzeit (HH:MM):");
+                    ui.text_edit_singleline(&mut row.time);
+                    egui::ComboBox::from_id_source(format!("row_action_{}", i))
+                        .selected_text(row.action.label())
+                        .show_ui(ui, |ui| {
+                            for action in Action::ALL {
+                                ui.selectable_value(&mut row.action, action, action.label());
+                            }
+                        });
+                    ui.label("Vorwarnung (Min., Komma-getrennt):");
+                    ui.add(egui::TextEdit::singleline(&mut row.warn_leads).desired_width(60.0));
+                    if ui.button("Entfernen").clicked() {
+                        remove_index = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove_index {
+                self.schedule_edit.remove(i);
+            }
+            if ui.button("Eintrag hinzufügen").clicked() {
+                let id = self.config.alloc_id();
+                self.schedule_edit.push(ScheduleRowEdit::new(id));
             }
             if ui.button("Zeitplan speichern").clicked() {
                 self.save_schedule();
             }
+
+            ui.separator();
+            // Intervall-Jobs ("alle N Stunden/Minuten"), unabhängig vom Wochentags-Zeitplan.
+            ui.heading("Intervall-Jobs");
+            let mut remove_interval_index = None;
+            for (i, row) in self.interval_edit.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label("Alle");
+                    ui.add(egui::TextEdit::singleline(&mut row.n).desired_width(30.0));
+                    egui::ComboBox::from_id_source(format!("interval_unit_{}", i))
+                        .selected_text(row.unit.label())
+                        .show_ui(ui, |ui| {
+                            for unit in [
+                                IntervalUnit::Seconds,
+                                IntervalUnit::Minutes,
+                                IntervalUnit::Hours,
+                                IntervalUnit::Days,
+                            ] {
+                                ui.selectable_value(&mut row.unit, unit, unit.label());
+                            }
+                        });
+                    ui.label("Anker (optional):");
+                    ui.add(egui::TextEdit::singleline(&mut row.at).desired_width(60.0));
+                    egui::ComboBox::from_id_source(format!("interval_action_{}", i))
+                        .selected_text(row.action.label())
+                        .show_ui(ui, |ui| {
+                            for action in Action::ALL {
+                                ui.selectable_value(&mut row.action, action, action.label());
+                            }
+                        });
+                    ui.label("Vorwarnung (Min., Komma-getrennt):");
+                    ui.add(egui::TextEdit::singleline(&mut row.warn_leads).desired_width(60.0));
+                    if ui.button("Entfernen").clicked() {
+                        remove_interval_index = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove_interval_index {
+                self.interval_edit.remove(i);
+            }
+            if ui.button("Intervall-Job hinzufügen").clicked() {
+                let id = self.config.alloc_id();
+                self.interval_edit.push(IntervalRowEdit::new(id));
+            }
+
+            ui.separator();
+            if ui.button("Alle geplanten Vorgänge abbrechen").clicked() {
+                self.reactor.cancel_all();
+                self.schedule_status = "Alle geplanten Vorgänge wurden abgebrochen.".to_owned();
+            }
         });
+
+        self.show_warning_dialogs(ctx);
     }
 }
 
@@ -296,6 +745,9 @@ fn main() {
     eframe::run_native(
         "Shutdown GUI App",
         native_options,
-        Box::new(|_cc| Box::new(app)),
+        Box::new(|cc| {
+            app.set_egui_ctx(cc.egui_ctx.clone());
+            Box::new(app)
+        }),
     );
 }
@@ -0,0 +1,352 @@
+//! Zentraler Scheduler-Reaktor: ein einzelner Hintergrund-Thread verwaltet
+//! alle anstehenden Ereignisse in einer Min-Heap, statt dass jeder Zeitplan
+//! seinen eigenen `thread::spawn`-Loop bekommt. Das macht Bearbeiten, Neuladen
+//! und Abbrechen korrekt, weil die Heap beim Speichern komplett ersetzt wird,
+//! anstatt zusätzliche Threads anzuhäufen.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+
+use crate::action::Action;
+use crate::config::EntryId;
+use crate::recurrence::Recurrence;
+
+pub type EventId = u64;
+
+/// Art eines Ereignisses: löst entweder direkt eine Aktion aus, oder ist eine
+/// Vorwarnung, die vor einem späteren Aktions-Ereignis feuert. `target`
+/// verweist dabei auf die Id dieses Aktions-Ereignisses, damit ein Postpone
+/// oder Abbrechen auf dem Warn-Dialog die richtige Aktion trifft.
+#[derive(Debug, Clone)]
+pub enum EventKind {
+    Action,
+    Warning { target: EventId, lead: ChronoDuration },
+}
+
+/// Ein einzelnes anstehendes Ereignis im Scheduler.
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub id: EventId,
+    pub next: DateTime<Local>,
+    pub action: Action,
+    pub recur: Recurrence,
+    /// Zeitplan-Eintrag, aus dem dieses Ereignis erzeugt wurde, falls es sich
+    /// nicht um einen manuell geplanten Vorgang handelt. Wird genutzt, um das
+    /// tatsächliche Feuern in `state.json` festzuhalten.
+    pub entry_id: Option<EntryId>,
+    pub kind: EventKind,
+    /// Vorlaufzeiten, zu denen vor dieser Aktion gewarnt werden soll, absteigend
+    /// sortiert. Nur an Aktions-Ereignissen relevant: beim (Neu-)Einplanen
+    /// erzeugt der Reaktor daraus automatisch begleitende Warn-Ereignisse.
+    pub warn_leads: Vec<ChronoDuration>,
+}
+
+// `BinaryHeap` ist ein Max-Heap; wir drehen die Ordnung um, damit der
+// früheste `next`-Zeitpunkt oben liegt (Min-Heap über die Fälligkeit).
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.next == other.next
+    }
+}
+impl Eq for Event {}
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.next.cmp(&self.next)
+    }
+}
+
+struct State {
+    heap: BinaryHeap<Event>,
+    /// Ob gerade ein `shutdown /s` oder `/r` bei Windows angemeldet ist, das per
+    /// `shutdown /a` wieder abgebrochen werden könnte.
+    armed_shutdown: bool,
+}
+
+type FiredCallback = dyn Fn(EntryId, DateTime<Local>) + Send + Sync;
+/// Wird aufgerufen, sobald ein Warn-Ereignis feuert: Ziel-Ereignis (die
+/// eigentliche Aktion), ihr Zeitplan-Eintrag, die Aktion selbst und die
+/// Vorlaufzeit dieser konkreten Warnung.
+type WarningCallback = dyn Fn(EventId, Option<EntryId>, Action, ChronoDuration) + Send + Sync;
+
+/// Besitzt die Heap aller anstehenden Ereignisse sowie den Hintergrund-Thread,
+/// der sie abarbeitet. Günstig zu klonen: teilt sich den internen `Arc`.
+#[derive(Clone)]
+pub struct Reactor {
+    state: Arc<(Mutex<State>, Condvar)>,
+    next_id: Arc<AtomicU64>,
+    on_fired: Arc<Mutex<Option<Box<FiredCallback>>>>,
+    on_warning: Arc<Mutex<Option<Box<WarningCallback>>>>,
+}
+
+impl Reactor {
+    /// Erzeugt einen neuen Reaktor und startet sofort seinen Hintergrund-Thread.
+    pub fn new() -> Self {
+        let reactor = Self {
+            state: Arc::new((
+                Mutex::new(State {
+                    heap: BinaryHeap::new(),
+                    armed_shutdown: false,
+                }),
+                Condvar::new(),
+            )),
+            next_id: Arc::new(AtomicU64::new(1)),
+            on_fired: Arc::new(Mutex::new(None)),
+            on_warning: Arc::new(Mutex::new(None)),
+        };
+        reactor.spawn_worker();
+        reactor
+    }
+
+    /// Registriert eine Callback, die aufgerufen wird, sobald ein
+    /// Zeitplan-Ereignis tatsächlich feuert. Dient dazu, `last_fired` in
+    /// `state.json` fortzuschreiben, damit verpasste Termine nie doppelt
+    /// nachgeholt werden.
+    pub fn set_on_fired<F>(&self, callback: F)
+    where
+        F: Fn(EntryId, DateTime<Local>) + Send + Sync + 'static,
+    {
+        *self.on_fired.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// Registriert eine Callback, die aufgerufen wird, sobald eine Vorwarnung
+    /// vor einer Aktion fällig wird. Dient der GUI dazu, einen nicht
+    /// blockierenden Countdown-Dialog mit Verschieben/Abbrechen anzuzeigen.
+    pub fn set_on_warning<F>(&self, callback: F)
+    where
+        F: Fn(EventId, Option<EntryId>, Action, ChronoDuration) + Send + Sync + 'static,
+    {
+        *self.on_warning.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// Liefert eine neue, für diesen Reaktor eindeutige Ereignis-Id.
+    pub fn next_id(&self) -> EventId {
+        self.next_id.fetch_add(1, AtomicOrdering::Relaxed)
+    }
+
+    /// Ersetzt die Menge der aus der Config stammenden Ereignisse durch
+    /// `events`. Wird beim Speichern des Zeitplans aufgerufen, damit
+    /// Bearbeiten und Neuladen nie Ereignisse verdoppeln. Manuell über
+    /// `push` eingeplante Aktionen (`entry_id: None`, z. B. ein einmaliger
+    /// manueller Shutdown) gehören nicht zur Config und werden deshalb
+    /// unangetastet übernommen, statt beim Aktivieren zu verschwinden.
+    /// Aktions-Ereignisse mit `warn_leads` bekommen dabei automatisch ihre
+    /// Warn-Ereignisse.
+    pub fn replace_all(&self, events: Vec<Event>) {
+        let (lock, cvar) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        let manual: Vec<Event> = state
+            .heap
+            .iter()
+            .filter(|event| event.entry_id.is_none() && matches!(event.kind, EventKind::Action))
+            .cloned()
+            .collect();
+        state.heap = events
+            .into_iter()
+            .chain(manual)
+            .flat_map(|event| expand_with_warnings(event, &self.next_id))
+            .collect();
+        cvar.notify_all();
+    }
+
+    /// Fügt ein einzelnes Ereignis hinzu, ohne bestehende zu verwerfen.
+    pub fn push(&self, event: Event) {
+        let (lock, cvar) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        for event in expand_with_warnings(event, &self.next_id) {
+            state.heap.push(event);
+        }
+        cvar.notify_all();
+    }
+
+    /// Verschiebt ein noch ausstehendes Aktions-Ereignis um `by` nach hinten
+    /// und plant seine Vorwarnungen neu. Wird von "Verschieben" auf dem
+    /// Warn-Dialog genutzt; hat keine Wirkung, wenn `target` bereits gefeuert
+    /// hat oder unbekannt ist.
+    pub fn postpone(&self, target: EventId, by: ChronoDuration) {
+        let (lock, cvar) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        let mut action_event = None;
+        let mut kept = BinaryHeap::new();
+        for event in state.heap.drain() {
+            let is_target_action = event.id == target && matches!(event.kind, EventKind::Action);
+            let is_sibling_warning =
+                matches!(&event.kind, EventKind::Warning { target: t, .. } if *t == target);
+            if is_target_action {
+                action_event = Some(event);
+            } else if !is_sibling_warning {
+                kept.push(event);
+            }
+        }
+        state.heap = kept;
+        if let Some(mut event) = action_event {
+            event.next = event.next + by;
+            for event in expand_with_warnings(event, &self.next_id) {
+                state.heap.push(event);
+            }
+        }
+        cvar.notify_all();
+    }
+
+    /// Entfernt ein einzelnes Ereignis anhand seiner Id, inklusive etwaiger
+    /// Vorwarnungen, die auf sie verweisen, und bricht einen bereits
+    /// angemeldeten Shutdown ab, falls nötig.
+    pub fn cancel(&self, id: EventId) {
+        let (lock, cvar) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        state.heap.retain(|event| {
+            event.id != id
+                && !matches!(&event.kind, EventKind::Warning { target, .. } if *target == id)
+        });
+        disarm_if_needed(&mut state);
+        cvar.notify_all();
+    }
+
+    /// Entfernt alle anstehenden Ereignisse und bricht einen ggf. bereits
+    /// angemeldeten Shutdown ab.
+    pub fn cancel_all(&self) {
+        let (lock, cvar) = &*self.state;
+        let mut state = lock.lock().unwrap();
+        state.heap.clear();
+        disarm_if_needed(&mut state);
+        cvar.notify_all();
+    }
+
+    fn spawn_worker(&self) {
+        let state = Arc::clone(&self.state);
+        let on_fired = Arc::clone(&self.on_fired);
+        let on_warning = Arc::clone(&self.on_warning);
+        let next_id = Arc::clone(&self.next_id);
+        thread::spawn(move || loop {
+            let (lock, cvar) = &*state;
+            let mut guard = lock.lock().unwrap();
+            loop {
+                match guard.heap.peek() {
+                    None => {
+                        // Nichts eingeplant: auf die nächste Änderung warten.
+                        guard = cvar.wait(guard).unwrap();
+                    }
+                    Some(top) => {
+                        let now = Local::now();
+                        if top.next <= now {
+                            break;
+                        }
+                        let wait_for = (top.next - now)
+                            .to_std()
+                            .unwrap_or(StdDuration::from_secs(0));
+                        let (g, timeout) = cvar.wait_timeout(guard, wait_for).unwrap();
+                        guard = g;
+                        if timeout.timed_out() {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            let due = match guard.heap.peek() {
+                Some(top) if top.next <= Local::now() => guard.heap.pop(),
+                _ => None,
+            };
+
+            if let Some(event) = due {
+                match &event.kind {
+                    EventKind::Action => {
+                        let armed = event.action.is_abortable();
+                        if armed {
+                            guard.armed_shutdown = true;
+                        }
+                        if let Some(next) = event.recur.next_after(event.next) {
+                            let next_event = Event {
+                                next,
+                                ..event.clone()
+                            };
+                            for event in expand_with_warnings(next_event, &next_id) {
+                                guard.heap.push(event);
+                            }
+                        }
+                        drop(guard);
+                        if let Some(entry_id) = event.entry_id {
+                            if let Some(callback) = &*on_fired.lock().unwrap() {
+                                callback(entry_id, event.next);
+                            }
+                        }
+                        event.action.run();
+                        if armed {
+                            // Nach Ablauf der Gnadenfrist hat Windows bereits
+                            // abgeschaltet bzw. neu gestartet; `shutdown /a`
+                            // liefe danach ins Leere, also die Markierung
+                            // wieder zurücknehmen, statt sie dauerhaft stehen
+                            // zu lassen.
+                            let state = Arc::clone(&state);
+                            thread::spawn(move || {
+                                thread::sleep(crate::action::ABORT_GRACE);
+                                let (lock, cvar) = &*state;
+                                let mut guard = lock.lock().unwrap();
+                                guard.armed_shutdown = false;
+                                cvar.notify_all();
+                            });
+                        }
+                    }
+                    EventKind::Warning { target, lead } => {
+                        let target = *target;
+                        let lead = *lead;
+                        drop(guard);
+                        if let Some(callback) = &*on_warning.lock().unwrap() {
+                            callback(target, event.entry_id, event.action, lead);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Baut zu einem Aktions-Ereignis seine begleitenden Warn-Ereignisse gemäß
+/// `warn_leads` und gibt beide zusammen zurück. Warnungen, deren Zeitpunkt
+/// bereits verstrichen wäre (z. B. bei sehr kurzen Intervallen), werden
+/// ausgelassen. Nicht-Aktions-Ereignisse werden unverändert durchgereicht.
+fn expand_with_warnings(event: Event, next_id: &AtomicU64) -> Vec<Event> {
+    let mut events = Vec::new();
+    if matches!(event.kind, EventKind::Action) {
+        let now = Local::now();
+        for lead in &event.warn_leads {
+            let warn_at = event.next - *lead;
+            if warn_at > now {
+                events.push(Event {
+                    id: next_id.fetch_add(1, AtomicOrdering::Relaxed),
+                    next: warn_at,
+                    action: event.action,
+                    recur: Recurrence::Once,
+                    entry_id: event.entry_id,
+                    kind: EventKind::Warning {
+                        target: event.id,
+                        lead: *lead,
+                    },
+                    warn_leads: Vec::new(),
+                });
+            }
+        }
+    }
+    events.push(event);
+    events
+}
+
+/// Bricht einen bereits über `shutdown /s` bzw. `/r` angemeldeten Shutdown
+/// oder Neustart wieder ab, falls einer aussteht.
+fn disarm_if_needed(state: &mut State) {
+    if state.armed_shutdown {
+        let _ = Command::new("shutdown").args(&["/a"]).status();
+        state.armed_shutdown = false;
+    }
+}
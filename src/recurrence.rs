@@ -0,0 +1,268 @@
+//! Wiederholungsregeln für Scheduler-Ereignisse. Neben festen Wochentagen
+//! unterstützt dies auch fluent gebaute Intervall-Jobs (`every(3).hours()`),
+//! angelehnt an das Job-Modell der Python-Bibliothek `skedge`.
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, Timelike, Weekday};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Zeiteinheit eines Intervall-Jobs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntervalUnit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl IntervalUnit {
+    fn duration(self, n: u32) -> ChronoDuration {
+        match self {
+            IntervalUnit::Seconds => ChronoDuration::seconds(n as i64),
+            IntervalUnit::Minutes => ChronoDuration::minutes(n as i64),
+            IntervalUnit::Hours => ChronoDuration::hours(n as i64),
+            IntervalUnit::Days => ChronoDuration::days(n as i64),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            IntervalUnit::Seconds => "Sekunden",
+            IntervalUnit::Minutes => "Minuten",
+            IntervalUnit::Hours => "Stunden",
+            IntervalUnit::Days => "Tage",
+        }
+    }
+}
+
+/// Legt fest, ob und wann ein Ereignis nach dem Feuern erneut eingeplant wird.
+#[derive(Debug, Clone)]
+pub enum Recurrence {
+    /// Einmaliges Ereignis, wird nach dem Feuern nicht erneut eingeplant.
+    Once,
+    /// Wiederholt sich jede Woche am selben Wochentag zur selben Uhrzeit.
+    Weekly(Weekday),
+    /// Wiederholt sich alle `n` Einheiten von `unit`, optional ausgerichtet
+    /// auf einen festen Zeitpunkt innerhalb der übergeordneten Einheit
+    /// (z. B. Minuten-Jobs immer zur selben Sekunde).
+    Interval {
+        unit: IntervalUnit,
+        n: u32,
+        anchor: Option<ChronoDuration>,
+    },
+}
+
+impl Recurrence {
+    /// Berechnet die nächste Fälligkeit, ausgehend vom zuletzt geplanten Zeitpunkt.
+    /// Gibt `None` zurück, wenn das Ereignis nicht erneut eingeplant werden soll.
+    pub fn next_after(&self, fired: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Recurrence::Once => None,
+            Recurrence::Weekly(_) => Some(fired + ChronoDuration::days(7)),
+            Recurrence::Interval { unit, n, anchor } => {
+                let step = unit.duration(*n);
+                let mut next = fired + step;
+                if let Some(anchor) = anchor {
+                    next = align_to_anchor(next, *unit, *anchor);
+                }
+                // Der PC kann während mehrerer Perioden geschlafen haben; statt
+                // jede verpasste Periode nachzuholen, direkt zum ersten
+                // Zeitpunkt nach `now` vorspulen, damit der Reaktor nicht in
+                // einer engen Schleife sofort mehrfach feuert.
+                let now = Local::now();
+                if next <= now {
+                    let missed = (now - next).num_milliseconds() / step.num_milliseconds().max(1);
+                    next += step * (missed as i32 + 1);
+                    if let Some(anchor) = anchor {
+                        next = align_to_anchor(next, *unit, *anchor);
+                    }
+                    while next <= now {
+                        next += step;
+                        if let Some(anchor) = anchor {
+                            next = align_to_anchor(next, *unit, *anchor);
+                        }
+                    }
+                }
+                Some(next)
+            }
+        }
+    }
+}
+
+/// Richtet `t` auf den Anker innerhalb seiner Einheit aus: für Minuten-Jobs
+/// wird z. B. die Sekunde auf den Anker gesetzt, bei gleichbleibender Minute.
+fn align_to_anchor(t: DateTime<Local>, unit: IntervalUnit, anchor: ChronoDuration) -> DateTime<Local> {
+    let period_start = match unit {
+        IntervalUnit::Days => t.date().and_hms(0, 0, 0),
+        IntervalUnit::Hours => t.date().and_hms(t.hour(), 0, 0),
+        IntervalUnit::Minutes => t.date().and_hms(t.hour(), t.minute(), 0),
+        IntervalUnit::Seconds => t,
+    };
+    period_start + anchor
+}
+
+/// Fluent Job-Builder für Intervall-Jobs, z. B. `every(3).hours().build()`
+/// oder `every(30).minutes().at(":15")?.build()`.
+pub struct Job {
+    n: u32,
+    unit: Option<IntervalUnit>,
+    anchor: Option<ChronoDuration>,
+}
+
+/// Startet einen neuen Intervall-Job: `every(3).hours()`.
+pub fn every(n: u32) -> Job {
+    Job {
+        n,
+        unit: None,
+        anchor: None,
+    }
+}
+
+impl Job {
+    pub fn seconds(mut self) -> Self {
+        self.unit = Some(IntervalUnit::Seconds);
+        self
+    }
+
+    pub fn minutes(mut self) -> Self {
+        self.unit = Some(IntervalUnit::Minutes);
+        self
+    }
+
+    pub fn hours(mut self) -> Self {
+        self.unit = Some(IntervalUnit::Hours);
+        self
+    }
+
+    pub fn days(mut self) -> Self {
+        self.unit = Some(IntervalUnit::Days);
+        self
+    }
+
+    /// Validiert einen Anker-Zeitpunkt innerhalb der zuvor gewählten Einheit
+    /// und setzt ihn. Das Format ist je Einheit unterschiedlich:
+    /// Tage `[HH:]MM:SS`, Stunden `[MM]:SS`, Minuten `:SS`.
+    pub fn at(mut self, spec: &str) -> Result<Self, String> {
+        let unit = self
+            .unit
+            .ok_or_else(|| "Einheit muss vor .at() gewählt werden".to_string())?;
+        self.anchor = Some(parse_anchor(unit, spec)?);
+        Ok(self)
+    }
+
+    /// Baut die fertige `Recurrence`, sofern eine Einheit gewählt wurde.
+    pub fn build(self) -> Result<Recurrence, String> {
+        let unit = self
+            .unit
+            .ok_or_else(|| "Einheit fehlt (z. B. .hours())".to_string())?;
+        Ok(Recurrence::Interval {
+            unit,
+            n: self.n,
+            anchor: self.anchor,
+        })
+    }
+}
+
+fn parse_anchor(unit: IntervalUnit, spec: &str) -> Result<ChronoDuration, String> {
+    let spec = spec.trim();
+    match unit {
+        IntervalUnit::Days => {
+            let re = Regex::new(r"^(?:([0-2]\d):)?([0-5]\d):([0-5]\d)$").unwrap();
+            let caps = re
+                .captures(spec)
+                .ok_or_else(|| format!("Ungültige Ankerzeit '{}' für Tage, erwartet [HH:]MM:SS", spec))?;
+            let h: i64 = caps.get(1).map_or(0, |m| m.as_str().parse().unwrap());
+            let m: i64 = caps[2].parse().unwrap();
+            let s: i64 = caps[3].parse().unwrap();
+            Ok(ChronoDuration::seconds(h * 3600 + m * 60 + s))
+        }
+        IntervalUnit::Hours => {
+            let re = Regex::new(r"^([0-5]\d)?:([0-5]\d)$").unwrap();
+            let caps = re
+                .captures(spec)
+                .ok_or_else(|| format!("Ungültige Ankerzeit '{}' für Stunden, erwartet [MM]:SS", spec))?;
+            let m: i64 = caps.get(1).map_or(0, |mm| mm.as_str().parse().unwrap());
+            let s: i64 = caps[2].parse().unwrap();
+            Ok(ChronoDuration::seconds(m * 60 + s))
+        }
+        IntervalUnit::Minutes => {
+            let re = Regex::new(r"^:([0-5]\d)$").unwrap();
+            let caps = re
+                .captures(spec)
+                .ok_or_else(|| format!("Ungültige Ankerzeit '{}' für Minuten, erwartet :SS", spec))?;
+            let s: i64 = caps[1].parse().unwrap();
+            Ok(ChronoDuration::seconds(s))
+        }
+        IntervalUnit::Seconds => Err("Sekunden-Jobs unterstützen keinen Anker".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn once_never_reschedules() {
+        assert!(Recurrence::Once.next_after(Local::now()).is_none());
+    }
+
+    #[test]
+    fn weekly_next_after_adds_seven_days() {
+        let fired = Local::now();
+        let next = Recurrence::Weekly(Weekday::Mon).next_after(fired).unwrap();
+        assert_eq!(next, fired + ChronoDuration::days(7));
+    }
+
+    #[test]
+    fn interval_next_after_without_miss_just_adds_one_period() {
+        let fired = Local::now();
+        let recur = Recurrence::Interval {
+            unit: IntervalUnit::Minutes,
+            n: 30,
+            anchor: None,
+        };
+        assert_eq!(recur.next_after(fired).unwrap(), fired + ChronoDuration::minutes(30));
+    }
+
+    /// Der PC hat mehrere Perioden verschlafen (hier: 10h Pause bei einem
+    /// 3h-Intervall, also 3 verpasste Feuerungen). `next_after` darf nicht
+    /// jede davon einzeln nachholen, sondern muss direkt zur ersten
+    /// Fälligkeit nach `now` vorspulen.
+    #[test]
+    fn interval_next_after_fast_forwards_past_slept_through_periods() {
+        let now = Local::now();
+        let fired = now - ChronoDuration::hours(10);
+        let recur = Recurrence::Interval {
+            unit: IntervalUnit::Hours,
+            n: 3,
+            anchor: None,
+        };
+        let next = recur.next_after(fired).unwrap();
+        assert!(next > now, "nächste Fälligkeit muss in der Zukunft liegen: {}", next);
+        assert!(
+            next <= now + ChronoDuration::hours(3),
+            "darf höchstens eine Periode über jetzt hinausschießen: {}",
+            next
+        );
+    }
+
+    #[test]
+    fn parse_anchor_accepts_unit_appropriate_formats() {
+        assert_eq!(parse_anchor(IntervalUnit::Minutes, ":15").unwrap(), ChronoDuration::seconds(15));
+        assert_eq!(
+            parse_anchor(IntervalUnit::Hours, "05:15").unwrap(),
+            ChronoDuration::seconds(5 * 60 + 15)
+        );
+        assert_eq!(
+            parse_anchor(IntervalUnit::Days, "06:05:15").unwrap(),
+            ChronoDuration::seconds(6 * 3600 + 5 * 60 + 15)
+        );
+    }
+
+    #[test]
+    fn parse_anchor_rejects_mismatched_formats() {
+        assert!(parse_anchor(IntervalUnit::Minutes, "05:15").is_err());
+        assert!(parse_anchor(IntervalUnit::Seconds, ":15").is_err());
+        assert!(parse_anchor(IntervalUnit::Hours, "5:15").is_err());
+    }
+}
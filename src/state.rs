@@ -0,0 +1,33 @@
+//! Persistenter Laufzeitzustand zwischen Programmstarts. Im Unterschied zur
+//! Config (was soll geplant werden) hält dies fest, wann ein Eintrag
+//! tatsächlich zuletzt gefeuert hat, damit verpasste Termine beim nächsten
+//! Start nachgeholt und nicht doppelt ausgeführt werden.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::config::EntryId;
+
+const STATE_FILE: &str = "state.json";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RunState {
+    pub last_fired: HashMap<EntryId, DateTime<Local>>,
+}
+
+/// Lädt den Zustand aus state.json oder erzeugt einen leeren Default.
+pub fn load_state() -> RunState {
+    fs::read_to_string(STATE_FILE)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Speichert den Zustand in state.json.
+pub fn save_state(state: &RunState) {
+    if let Ok(json) = serde_json::to_string_pretty(state) {
+        let _ = fs::write(STATE_FILE, json);
+    }
+}